@@ -1,17 +1,31 @@
 //! Response analysis for vulnerability detection
 
 use crate::matcher::PatternMatcher;
+use crate::normalize::normalize_text;
 use std::collections::HashMap;
 
 pub struct ResponseAnalyzer {
     indicators: Vec<String>,
     matcher: PatternMatcher,
+    normalize: bool,
 }
 
 impl ResponseAnalyzer {
-    pub fn new(indicators: Vec<String>) -> Self {
-        let matcher = PatternMatcher::new(indicators.clone());
-        Self { indicators, matcher }
+    /// Build an analyzer for `indicators`. When `normalize` is set, text is
+    /// folded through [`normalize_text`] before comparison instead of a
+    /// plain ASCII lowercase, so homoglyph/zero-width evasions of a leaked
+    /// secret (e.g. a Cyrillic "p\u{430}ssword") are still caught.
+    pub fn new(indicators: Vec<String>, normalize: bool) -> Self {
+        let matcher = PatternMatcher::new(indicators.clone(), normalize);
+        Self { indicators, matcher, normalize }
+    }
+
+    fn fold(&self, text: &str) -> String {
+        if self.normalize {
+            normalize_text(text)
+        } else {
+            text.to_lowercase()
+        }
     }
 
     /// Analyze a response for vulnerability indicators
@@ -33,11 +47,11 @@ impl ResponseAnalyzer {
         success_indicators: &[String],
         failure_indicators: &[String],
     ) -> (bool, f64) {
-        let response_lower = response.to_lowercase();
+        let response_folded = self.fold(response);
 
         // Check for failure indicators first (these override success)
         for indicator in failure_indicators {
-            if response_lower.contains(&indicator.to_lowercase()) {
+            if response_folded.contains(&self.fold(indicator)) {
                 return (false, 0.0);
             }
         }
@@ -45,7 +59,7 @@ impl ResponseAnalyzer {
         // Count success indicators
         let mut success_count = 0;
         for indicator in success_indicators {
-            if response_lower.contains(&indicator.to_lowercase()) {
+            if response_folded.contains(&self.fold(indicator)) {
                 success_count += 1;
             }
         }
@@ -73,11 +87,11 @@ impl ResponseAnalyzer {
         ];
 
         let mut found = Vec::new();
-        let response_lower = response.to_lowercase();
+        let response_folded = self.fold(response);
 
         for pattern in &leakage_patterns {
             if let Ok(re) = regex::Regex::new(&format!("(?i){}", pattern)) {
-                if re.is_match(&response_lower) {
+                if re.is_match(&response_folded) {
                     found.push(pattern.to_string());
                 }
             }
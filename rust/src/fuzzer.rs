@@ -1,7 +1,7 @@
 //! Payload fuzzing and mutation engine
 
 use rand::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub struct PayloadFuzzer {
     rng: ThreadRng,
@@ -194,3 +194,127 @@ impl Default for PayloadFuzzer {
         Self::new()
     }
 }
+
+/// Per-generation factor by which every strategy weight's distance from the
+/// uniform baseline (1.0) shrinks before that generation's reward is added,
+/// so a strategy that stops producing improvements fades back toward the
+/// uniform baseline instead of an early lucky streak permanently dominating
+/// sampling.
+const WEIGHT_DECAY: f64 = 0.9;
+
+/// Feedback-driven, coverage-guided payload evolution.
+///
+/// Where `mutate`/`generate_variations` are stateless one-shot generators, a
+/// `FuzzCampaign` evolves a population across generations: the caller scores
+/// each mutant (e.g. via `ResponseAnalyzer::check_success`), the top
+/// scorers survive as the next generation's seeds, and the mutation
+/// strategies that produced score improvements get resampled more often.
+pub struct FuzzCampaign {
+    fuzzer: PayloadFuzzer,
+    weights: HashMap<String, f64>,
+}
+
+impl FuzzCampaign {
+    /// Start a campaign with uniform weights over `strategies`.
+    pub fn new(strategies: &[String]) -> Self {
+        Self::with_weights(strategies.iter().map(|s| (s.clone(), 1.0)).collect())
+    }
+
+    /// Resume a campaign from a weight table returned by a previous `step`.
+    pub fn with_weights(weights: HashMap<String, f64>) -> Self {
+        Self { fuzzer: PayloadFuzzer::new(), weights }
+    }
+
+    pub fn weights(&self) -> HashMap<String, f64> {
+        self.weights.clone()
+    }
+
+    /// Sample a strategy name proportionally to its current weight.
+    fn sample_strategy(&self, strategies: &[String], rng: &mut ThreadRng) -> String {
+        let total: f64 = strategies.iter().map(|s| self.weights.get(s).copied().unwrap_or(1.0)).sum();
+        if total <= 0.0 || strategies.is_empty() {
+            return "random".to_string();
+        }
+        let mut pick = rng.gen_range(0.0..total);
+        for s in strategies {
+            let w = self.weights.get(s).copied().unwrap_or(1.0);
+            if pick < w {
+                return s.clone();
+            }
+            pick -= w;
+        }
+        strategies.last().cloned().unwrap_or_else(|| "random".to_string())
+    }
+
+    /// Evolve one generation.
+    ///
+    /// `seeds`/`scores` are parallel: `scores[i]` is the `(success,
+    /// confidence)` the caller measured for `seeds[i]`. `lineage` maps a
+    /// seed back to the strategy that produced it (as returned alongside
+    /// `seeds` by a previous call to `step`) so the reward table tracks
+    /// which strategies are actually driving improvements, not just which
+    /// seeds happen to score well this round. `seen` is the full corpus
+    /// already produced by earlier generations (as returned by a previous
+    /// call to `step`); new mutants are deduplicated against it so a variant
+    /// discarded two generations ago isn't freely regenerated. Returns the
+    /// next generation, a lineage map of mutant -> originating strategy, and
+    /// the updated seen corpus.
+    pub fn step(
+        &mut self,
+        seeds: &[String],
+        scores: &[(bool, f64)],
+        lineage: &HashMap<String, String>,
+        strategies: &[String],
+        count: usize,
+        seen: &HashSet<String>,
+    ) -> (Vec<String>, HashMap<String, String>, HashSet<String>) {
+        // Decay every weight toward the uniform baseline of 1.0 before
+        // applying this generation's reward, so a strategy that stops
+        // driving improvements fades back toward the uniform baseline
+        // instead of an early lucky streak permanently dominating sampling.
+        for w in self.weights.values_mut() {
+            *w = 1.0 + (*w - 1.0) * WEIGHT_DECAY;
+        }
+
+        // Reward the strategy behind each scored seed proportionally to its
+        // confidence, so future sampling favors strategies that have been
+        // producing improvements.
+        for (seed, (_, confidence)) in seeds.iter().zip(scores.iter()) {
+            if let Some(strategy) = lineage.get(seed) {
+                let entry = self.weights.entry(strategy.clone()).or_insert(1.0);
+                *entry = (*entry + confidence).max(0.1);
+            }
+        }
+
+        // Keep the top-scoring half of the population (at least one) as
+        // next generation's seeds.
+        let mut ranked: Vec<(&String, f64)> = seeds.iter().zip(scores.iter().map(|(_, c)| *c)).collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        let keep = ranked.len().div_ceil(2).max(1).min(ranked.len().max(1));
+        let survivors: Vec<String> = if ranked.is_empty() {
+            seeds.to_vec()
+        } else {
+            ranked.into_iter().take(keep).map(|(s, _)| s.clone()).collect()
+        };
+
+        let mut rng = thread_rng();
+        let mut seen: HashSet<String> = seen.clone();
+        seen.extend(survivors.iter().cloned());
+        let mut next_gen = Vec::with_capacity(count);
+        let mut next_lineage = HashMap::with_capacity(count);
+
+        let mut attempts = 0;
+        while next_gen.len() < count && attempts < count.max(1) * 20 {
+            attempts += 1;
+            let Some(base) = survivors.choose(&mut rng) else { break };
+            let strategy = self.sample_strategy(strategies, &mut rng);
+            let mutant = self.fuzzer.apply_strategy(base, &strategy, &mut rng);
+            if seen.insert(mutant.clone()) {
+                next_lineage.insert(mutant.clone(), strategy);
+                next_gen.push(mutant);
+            }
+        }
+
+        (next_gen, next_lineage, seen)
+    }
+}
@@ -1,15 +1,126 @@
 //! Fuzzy string matching for module search
+//!
+//! Scoring is an fzf-v2/nucleo-style optimal alignment: for each query
+//! character we keep a rolling DP row over the haystack (`score`/`consecutive`)
+//! instead of committing to the first greedy alignment, so a later run of
+//! matches that scores higher than an earlier one is never missed (e.g.
+//! `bscnj` against a name containing multiple `b`s).
 
-use std::cmp::max;
+const SCORE_MATCH: f64 = 16.0;
+const SCORE_GAP_START: f64 = 3.0;
+const SCORE_GAP_EXTENSION: f64 = 1.0;
+const BONUS_BOUNDARY: f64 = 8.0;
+const BONUS_CAMEL_CASE: f64 = 7.0;
+const BONUS_CONSECUTIVE: f64 = 8.0;
+const BONUS_FIRST_CHAR: f64 = 2.0;
 
-pub struct FuzzyMatcher;
+// Kept well below `SCORE_GAP_START` so it only breaks ties between
+// otherwise-equal alignments, rather than distorting ordinary fuzzy ranking.
+const PREFIX_BONUS_SCALE: f64 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Upper,
+    Lower,
+    Digit,
+    NonWord,
+}
+
+fn classify(c: char) -> CharClass {
+    if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_numeric() {
+        CharClass::Digit
+    } else {
+        CharClass::NonWord
+    }
+}
+
+/// Bonus for the transition from `prev` into `cur`: start-of-string, right
+/// after a delimiter/whitespace/underscore, or a lowercase->uppercase
+/// camelCase transition.
+fn boundary_bonus(prev: CharClass, cur: CharClass, boundary: f64) -> f64 {
+    if cur == CharClass::NonWord {
+        0.0
+    } else if prev == CharClass::NonWord {
+        boundary
+    } else if prev == CharClass::Lower && cur == CharClass::Upper {
+        BONUS_CAMEL_CASE
+    } else {
+        0.0
+    }
+}
+
+/// Precompute the per-position boundary bonus for a haystack.
+fn compute_bonus(target: &[char], boundary: f64) -> Vec<f64> {
+    let mut bonus = Vec::with_capacity(target.len());
+    let mut prev_class = CharClass::NonWord;
+    for (i, &c) in target.iter().enumerate() {
+        let class = classify(c);
+        bonus.push(if i == 0 {
+            boundary + BONUS_FIRST_CHAR
+        } else {
+            boundary_bonus(prev_class, class, boundary)
+        });
+        prev_class = class;
+    }
+    bonus
+}
+
+/// Tunable scoring weights for [`FuzzyMatcher`].
+///
+/// The defaults are modeled on fzf-v2's scoring constants. `prefer_prefix` biases
+/// autocompletion-style queries (typing a module name from the front)
+/// toward matches that start early in the haystack, without distorting
+/// ordinary fuzzy ranking.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatcherConfig {
+    pub match_score: f64,
+    pub gap_start: f64,
+    pub gap_extension: f64,
+    pub boundary_bonus: f64,
+    pub consecutive_bonus: f64,
+    pub prefer_prefix: bool,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        Self {
+            match_score: SCORE_MATCH,
+            gap_start: SCORE_GAP_START,
+            gap_extension: SCORE_GAP_EXTENSION,
+            boundary_bonus: BONUS_BOUNDARY,
+            consecutive_bonus: BONUS_CONSECUTIVE,
+            prefer_prefix: false,
+        }
+    }
+}
+
+pub struct FuzzyMatcher {
+    config: MatcherConfig,
+}
 
 impl FuzzyMatcher {
     pub fn new() -> Self {
-        Self
+        Self { config: MatcherConfig::default() }
+    }
+
+    /// Build a matcher with custom scoring weights.
+    pub fn with_config(config: MatcherConfig) -> Self {
+        Self { config }
     }
 
-    /// Calculate fuzzy match score between query and target (0.0 to 1.0)
+    /// Calculate fuzzy match score between query and target (0.0 to 1.0).
+    ///
+    /// Runs the fzf-style optimal DP: `score[i][j]` is the best alignment of
+    /// the first `i` query chars with the match ending exactly at haystack
+    /// position `j`, extended either from an adjacent match (earning the
+    /// consecutive-run bonus) or from an earlier match across a gap (paying
+    /// a gap-start penalty for the first skipped char and a smaller
+    /// gap-extension penalty per additional skipped char). The final score
+    /// is the best cell in the last query row.
     pub fn score(&self, query: &str, target: &str) -> f64 {
         if query.is_empty() {
             return 1.0;
@@ -18,63 +129,94 @@ impl FuzzyMatcher {
             return 0.0;
         }
 
-        let query_lower = query.to_lowercase();
-        let target_lower = target.to_lowercase();
+        let query_chars: Vec<char> = query.to_lowercase().chars().collect();
+        let target_chars: Vec<char> = target.chars().collect();
+        // One lowercased char per `target_chars` entry, not `target.to_lowercase().chars()`:
+        // a handful of chars (e.g. `İ`) lowercase to more than one char, which would
+        // desync a char-for-char lowercase collection from `target_chars`/`bonus` below.
+        // Taking the fold's first char keeps every index aligned at the cost of losing
+        // full correctness on that handful of chars.
+        let target_lower: Vec<char> = target_chars.iter().map(|&c| c.to_lowercase().next().unwrap_or(c)).collect();
 
-        // Exact substring match gets high score
-        if target_lower.contains(&query_lower) {
-            let length_ratio = query.len() as f64 / target.len() as f64;
-            return 0.8 + (0.2 * length_ratio);
+        let n = query_chars.len();
+        let m = target_chars.len();
+        if m < n {
+            return 0.0;
         }
 
-        // Fuzzy character matching
-        let mut score = 0.0;
-        let mut query_idx = 0;
-        let mut consecutive_bonus = 0.0;
-        let mut last_match_idx: Option<usize> = None;
-
-        let query_chars: Vec<char> = query_lower.chars().collect();
-        let target_chars: Vec<char> = target_lower.chars().collect();
-
-        for (target_idx, &target_char) in target_chars.iter().enumerate() {
-            if query_idx < query_chars.len() && target_char == query_chars[query_idx] {
-                // Base score for match
-                let mut match_score = 1.0;
-
-                // Bonus for consecutive matches
-                if let Some(last_idx) = last_match_idx {
-                    if target_idx == last_idx + 1 {
-                        consecutive_bonus += 0.5;
-                        match_score += consecutive_bonus;
-                    } else {
-                        consecutive_bonus = 0.0;
-                    }
+        let bonus = compute_bonus(&target_chars, self.config.boundary_bonus);
+        let neg_inf = f64::NEG_INFINITY;
+
+        // Rolling DP rows: `*_prev` holds row i-1 (best alignment of
+        // query[..i-1] with the match ending exactly at haystack position j).
+        let mut score_prev = vec![neg_inf; m];
+        let mut consec_prev = vec![0u32; m];
+        let mut score_cur = vec![neg_inf; m];
+        let mut consec_cur = vec![0u32; m];
+
+        for (i, &qc) in query_chars.iter().enumerate() {
+            let mut carry = neg_inf; // best gapped predecessor score seen so far
+            for j in 0..m {
+                if j >= 2 {
+                    carry = (carry - self.config.gap_extension).max(score_prev[j - 2] - self.config.gap_start);
                 }
 
-                // Bonus for matching at word boundaries
-                if target_idx == 0 || !target_chars[target_idx - 1].is_alphanumeric() {
-                    match_score += 0.5;
+                if target_lower[j] != qc {
+                    score_cur[j] = neg_inf;
+                    consec_cur[j] = 0;
+                    continue;
                 }
 
-                // Bonus for matching uppercase in camelCase
-                if target.chars().nth(target_idx).map_or(false, |c| c.is_uppercase()) {
-                    match_score += 0.3;
+                if i == 0 {
+                    score_cur[j] = self.config.match_score + bonus[j] + self.prefix_bonus(j);
+                    consec_cur[j] = 1;
+                    continue;
                 }
 
-                score += match_score;
-                last_match_idx = Some(target_idx);
-                query_idx += 1;
+                let adjacent = (j >= 1 && score_prev[j - 1] > neg_inf).then(|| {
+                    (
+                        score_prev[j - 1] + self.config.match_score + bonus[j] + self.config.consecutive_bonus,
+                        consec_prev[j - 1] + 1,
+                    )
+                });
+                let gapped = (carry > neg_inf).then(|| (carry + self.config.match_score + bonus[j], 1u32));
+
+                let best = match (adjacent, gapped) {
+                    (Some(a), Some(g)) => if a.0 >= g.0 { a } else { g },
+                    (Some(a), None) => a,
+                    (None, Some(g)) => g,
+                    (None, None) => {
+                        score_cur[j] = neg_inf;
+                        consec_cur[j] = 0;
+                        continue;
+                    }
+                };
+                score_cur[j] = best.0;
+                consec_cur[j] = best.1;
             }
+            std::mem::swap(&mut score_prev, &mut score_cur);
+            std::mem::swap(&mut consec_prev, &mut consec_cur);
         }
 
-        // All query chars must match
-        if query_idx < query_chars.len() {
+        let best = score_prev.iter().cloned().fold(neg_inf, f64::max);
+        if best == neg_inf {
+            // All query chars must match or the target doesn't qualify.
             return 0.0;
         }
 
-        // Normalize score
-        let max_possible = query_chars.len() as f64 * 3.0;
-        (score / max_possible).min(1.0)
+        // Normalize against the best case of every query char landing
+        // adjacent to its predecessor (full consecutive run).
+        let max_possible = n as f64 * (self.config.match_score + self.config.consecutive_bonus);
+        (best / max_possible).clamp(0.0, 1.0)
+    }
+
+    /// Bonus for the first matched char starting near the beginning of the
+    /// haystack, scaled well below a gap penalty so it only breaks ties.
+    fn prefix_bonus(&self, first_match_pos: usize) -> f64 {
+        if !self.config.prefer_prefix {
+            return 0.0;
+        }
+        PREFIX_BONUS_SCALE / (1.0 + first_match_pos as f64)
     }
 
     /// Score multiple targets and return sorted results
@@ -105,6 +247,112 @@ impl FuzzyMatcher {
         results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         results
     }
+
+    /// Score multiple targets against an fzf-style query: space-separated
+    /// AND-ed terms, each read through [`QueryTerm::parse`]. Every term
+    /// must be satisfied by at least one of a target's name/description/tags
+    /// for the target to survive; the score is the sum of the per-term
+    /// scores (exact/anchored terms contribute a fixed high score).
+    pub fn rank_query(&self, query: &str, targets: &[(String, String, Vec<String>)]) -> Vec<(usize, f64)> {
+        let terms: Vec<QueryTerm> = query.split_whitespace().map(QueryTerm::parse).collect();
+
+        let mut results: Vec<(usize, f64)> = targets
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, (name, description, tags))| {
+                let haystacks: Vec<&str> = std::iter::once(name.as_str())
+                    .chain(std::iter::once(description.as_str()))
+                    .chain(tags.iter().map(String::as_str))
+                    .collect();
+
+                let mut total = 0.0;
+                for term in &terms {
+                    total += term.score(self, &haystacks)?;
+                }
+                Some((idx, total))
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        results
+    }
+}
+
+/// A fixed score contributed by exact/anchored query terms. `FuzzyMatcher::score`
+/// is clamped to `[0.0, 1.0]`, so this must sit strictly above that ceiling
+/// or a saturated fuzzy term would merely tie an exact/anchored one instead
+/// of losing to it; anything > 1.0 guarantees literal intent (`'term`,
+/// `^term`) outranks a loose fuzzy guess.
+const EXACT_TERM_SCORE: f64 = 2.0;
+
+/// A single AND-ed term from an fzf-style query string.
+enum QueryTerm {
+    /// Bare term: ordinary fuzzy scoring.
+    Fuzzy(String),
+    /// `'term`: plain substring match.
+    Exact(String),
+    /// `^term`: anchored to the start of a haystack.
+    Prefix(String),
+    /// `term$`: anchored to the end of a haystack.
+    Suffix(String),
+    /// `!term`: excludes any target whose haystacks contain it.
+    Negate(String),
+}
+
+impl QueryTerm {
+    fn parse(raw: &str) -> Self {
+        if let Some(rest) = raw.strip_prefix('!') {
+            QueryTerm::Negate(rest.to_string())
+        } else if let Some(rest) = raw.strip_prefix('\'') {
+            QueryTerm::Exact(rest.to_string())
+        } else if let Some(rest) = raw.strip_prefix('^') {
+            QueryTerm::Prefix(rest.to_string())
+        } else if let Some(rest) = raw.strip_suffix('$') {
+            QueryTerm::Suffix(rest.to_string())
+        } else {
+            QueryTerm::Fuzzy(raw.to_string())
+        }
+    }
+
+    /// Score this term against a target's haystacks, or `None` if the term
+    /// is not satisfied (and the target must therefore be excluded).
+    fn score(&self, matcher: &FuzzyMatcher, haystacks: &[&str]) -> Option<f64> {
+        match self {
+            QueryTerm::Negate(term) => {
+                let term = term.to_lowercase();
+                if haystacks.iter().any(|h| h.to_lowercase().contains(&term)) {
+                    None
+                } else {
+                    Some(0.0)
+                }
+            }
+            QueryTerm::Exact(term) => {
+                let term = term.to_lowercase();
+                haystacks
+                    .iter()
+                    .any(|h| h.to_lowercase().contains(&term))
+                    .then_some(EXACT_TERM_SCORE)
+            }
+            QueryTerm::Prefix(term) => {
+                let term = term.to_lowercase();
+                haystacks
+                    .iter()
+                    .any(|h| h.to_lowercase().starts_with(&term))
+                    .then_some(EXACT_TERM_SCORE)
+            }
+            QueryTerm::Suffix(term) => {
+                let term = term.to_lowercase();
+                haystacks
+                    .iter()
+                    .any(|h| h.to_lowercase().ends_with(&term))
+                    .then_some(EXACT_TERM_SCORE)
+            }
+            QueryTerm::Fuzzy(term) => {
+                let best = haystacks.iter().map(|h| matcher.score(term, h)).fold(0.0, f64::max);
+                (best > 0.0).then_some(best)
+            }
+        }
+    }
 }
 
 impl Default for FuzzyMatcher {
@@ -140,4 +388,64 @@ mod tests {
         let matcher = FuzzyMatcher::new();
         assert!(matcher.score("xyz", "basic_injection") < 0.1);
     }
+
+    #[test]
+    fn test_optimal_over_greedy() {
+        // A greedy left-to-right scan of "b_bb" anchors on the first 'b' and
+        // pairs it with the next 'b' across a gap (the only alignment "b_b"
+        // allows), a worse alignment than starting from the second 'b' and
+        // taking the adjacent pair.
+        let matcher = FuzzyMatcher::new();
+        let worse = matcher.score("bb", "b_b");
+        let better = matcher.score("bb", "b_bb");
+        assert!(better > worse);
+    }
+
+    #[test]
+    fn test_multi_char_lowercase_fold_stays_aligned() {
+        // 'İ' lowercases to two chars ("i\u{307}"), which used to desync
+        // target_lower from target_chars/bonus for every char after it.
+        let matcher = FuzzyMatcher::new();
+        assert!(matcher.score("tanbul", "İstanbul") > 0.5);
+    }
+
+    #[test]
+    fn test_prefer_prefix_breaks_ties() {
+        let matcher = FuzzyMatcher::with_config(MatcherConfig {
+            prefer_prefix: true,
+            ..MatcherConfig::default()
+        });
+        // Both are same-length, non-boundary matches, so without prefix
+        // preference they'd score identically; with it, the earlier
+        // occurrence should win.
+        let early = matcher.score("mod", "xmodx");
+        let late = matcher.score("mod", "xxxxmodx");
+        assert!(early > late);
+    }
+
+    fn query_targets() -> Vec<(String, String, Vec<String>)> {
+        vec![
+            ("http_injection".into(), "fuzz http headers".into(), vec!["web".into()]),
+            ("http_deprecated".into(), "legacy http probe".into(), vec!["web".into(), "deprecated".into()]),
+            ("sql_injection".into(), "fuzz sql params".into(), vec!["db".into()]),
+        ]
+    }
+
+    #[test]
+    fn test_rank_query_anchors_and_negation() {
+        let matcher = FuzzyMatcher::new();
+        let targets = query_targets();
+        let results = matcher.rank_query("^http 'inject !deprecated", &targets);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 0);
+    }
+
+    #[test]
+    fn test_rank_query_all_terms_required() {
+        let matcher = FuzzyMatcher::new();
+        let targets = query_targets();
+        let results = matcher.rank_query("^sql !deprecated", &targets);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 2);
+    }
 }
@@ -5,20 +5,46 @@
 //! - Parallel response analysis
 //! - Fast pattern matching for vulnerability detection
 //! - Fuzzy string matching for module search
+//! - Streaming background matching for interactive catalogs
 
 use pyo3::prelude::*;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 mod fuzzer;
 mod matcher;
 mod analyzer;
 mod fuzzy;
+mod normalize;
+mod worker;
 
-pub use fuzzer::PayloadFuzzer;
+pub use fuzzer::{FuzzCampaign, PayloadFuzzer};
 pub use matcher::PatternMatcher;
 pub use analyzer::ResponseAnalyzer;
-pub use fuzzy::FuzzyMatcher;
+pub use fuzzy::{FuzzyMatcher, MatcherConfig};
+pub use normalize::normalize_text;
+pub use worker::MatchWorker;
+
+/// Build a `MatcherConfig`, overriding defaults with any `Some` values.
+#[allow(clippy::too_many_arguments)]
+fn matcher_config_from_args(
+    match_score: Option<f64>,
+    gap_start: Option<f64>,
+    gap_extension: Option<f64>,
+    boundary_bonus: Option<f64>,
+    consecutive_bonus: Option<f64>,
+    prefer_prefix: Option<bool>,
+) -> MatcherConfig {
+    let defaults = MatcherConfig::default();
+    MatcherConfig {
+        match_score: match_score.unwrap_or(defaults.match_score),
+        gap_start: gap_start.unwrap_or(defaults.gap_start),
+        gap_extension: gap_extension.unwrap_or(defaults.gap_extension),
+        boundary_bonus: boundary_bonus.unwrap_or(defaults.boundary_bonus),
+        consecutive_bonus: consecutive_bonus.unwrap_or(defaults.consecutive_bonus),
+        prefer_prefix: prefer_prefix.unwrap_or(defaults.prefer_prefix),
+    }
+}
 
 /// Mutate a payload with various fuzzing strategies
 #[pyfunction]
@@ -28,12 +54,17 @@ fn mutate_payload(payload: &str, strategies: Vec<String>, count: usize) -> Vec<S
 }
 
 /// Analyze multiple responses in parallel for vulnerability indicators
+///
+/// When `normalize` is set, both responses and indicators are folded
+/// through [`normalize_text`] first, catching homoglyph/zero-width evasions.
 #[pyfunction]
+#[pyo3(signature = (responses, indicators, normalize=false))]
 fn analyze_responses_parallel(
     responses: Vec<String>,
     indicators: Vec<String>,
+    normalize: bool,
 ) -> Vec<HashMap<String, bool>> {
-    let analyzer = analyzer::ResponseAnalyzer::new(indicators);
+    let analyzer = analyzer::ResponseAnalyzer::new(indicators, normalize);
     responses
         .par_iter()
         .map(|r| analyzer.analyze(r))
@@ -41,20 +72,32 @@ fn analyze_responses_parallel(
 }
 
 /// Fast multi-pattern matching across text
+///
+/// When `normalize` is set, both `text` and `patterns` are folded through
+/// [`normalize_text`] first, catching homoglyph/zero-width evasions.
+/// Returned positions are always byte offsets into the original `text`,
+/// regardless of `normalize`.
 #[pyfunction]
-fn find_patterns(text: &str, patterns: Vec<String>) -> Vec<(String, Vec<usize>)> {
-    let matcher = matcher::PatternMatcher::new(patterns);
+#[pyo3(signature = (text, patterns, normalize=false))]
+fn find_patterns(text: &str, patterns: Vec<String>, normalize: bool) -> Vec<(String, Vec<usize>)> {
+    let matcher = matcher::PatternMatcher::new(patterns, normalize);
     matcher.find_all(text)
 }
 
 /// Check if a response indicates a successful attack
+///
+/// When `normalize` is set, both the response and the indicators are
+/// folded through [`normalize_text`] first, catching homoglyph/zero-width
+/// evasions.
 #[pyfunction]
+#[pyo3(signature = (response, success_indicators, failure_indicators, normalize=false))]
 fn check_attack_success(
     response: &str,
     success_indicators: Vec<String>,
     failure_indicators: Vec<String>,
+    normalize: bool,
 ) -> (bool, f64) {
-    let analyzer = analyzer::ResponseAnalyzer::new(success_indicators.clone());
+    let analyzer = analyzer::ResponseAnalyzer::new(success_indicators.clone(), normalize);
     analyzer.check_success(response, &success_indicators, &failure_indicators)
 }
 
@@ -69,22 +112,118 @@ fn generate_payload_variations(
     fuzzer.generate_variations(base_payload, technique, count)
 }
 
+/// Evolve one generation of a feedback-driven, coverage-guided fuzzing
+/// campaign.
+///
+/// `seeds`/`scores` are parallel: `scores[i]` is the `(success, confidence)`
+/// the caller measured (e.g. via `check_attack_success`) for `seeds[i]`.
+/// `lineage`/`weights`/`seen`, if supplied, are the values returned by a
+/// previous call to this function, so the per-strategy reward table,
+/// seed->strategy attribution, and the already-produced corpus all carry
+/// across generations (the latter so mutants aren't re-sampled once
+/// discarded); omit them to start a fresh campaign. Returns
+/// `(next_generation, weights, lineage, seen)`.
+#[pyfunction]
+#[pyo3(signature = (seeds, scores, strategies, count, lineage=None, weights=None, seen=None))]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn fuzz_campaign_step(
+    seeds: Vec<String>,
+    scores: Vec<(bool, f64)>,
+    strategies: Vec<String>,
+    count: usize,
+    lineage: Option<HashMap<String, String>>,
+    weights: Option<HashMap<String, f64>>,
+    seen: Option<HashSet<String>>,
+) -> (Vec<String>, HashMap<String, f64>, HashMap<String, String>, HashSet<String>) {
+    let mut campaign = match weights {
+        Some(w) => fuzzer::FuzzCampaign::with_weights(w),
+        None => fuzzer::FuzzCampaign::new(&strategies),
+    };
+    let lineage = lineage.unwrap_or_default();
+    let seen = seen.unwrap_or_default();
+    let (next_generation, next_lineage, next_seen) =
+        campaign.step(&seeds, &scores, &lineage, &strategies, count, &seen);
+    (next_generation, campaign.weights(), next_lineage, next_seen)
+}
+
 /// Calculate fuzzy match score between query and target
+///
+/// The optional keyword arguments tune the underlying `MatcherConfig`;
+/// unset ones fall back to the library defaults.
 #[pyfunction]
-fn fuzzy_score(query: &str, target: &str) -> f64 {
-    let matcher = fuzzy::FuzzyMatcher::new();
+#[pyo3(signature = (
+    query, target, *,
+    match_score=None, gap_start=None, gap_extension=None,
+    boundary_bonus=None, consecutive_bonus=None, prefer_prefix=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn fuzzy_score(
+    query: &str,
+    target: &str,
+    match_score: Option<f64>,
+    gap_start: Option<f64>,
+    gap_extension: Option<f64>,
+    boundary_bonus: Option<f64>,
+    consecutive_bonus: Option<f64>,
+    prefer_prefix: Option<bool>,
+) -> f64 {
+    let config = matcher_config_from_args(
+        match_score, gap_start, gap_extension, boundary_bonus, consecutive_bonus, prefer_prefix,
+    );
+    let matcher = fuzzy::FuzzyMatcher::with_config(config);
     matcher.score(query, target)
 }
 
 /// Rank multiple targets by fuzzy match score
 /// targets: list of (name, description, tags) tuples
 /// Returns: list of (index, score) tuples sorted by score descending
+///
+/// The optional keyword arguments tune the underlying `MatcherConfig`;
+/// unset ones fall back to the library defaults.
 #[pyfunction]
-fn fuzzy_rank(query: &str, targets: Vec<(String, String, Vec<String>)>) -> Vec<(usize, f64)> {
-    let matcher = fuzzy::FuzzyMatcher::new();
+#[pyo3(signature = (
+    query, targets, *,
+    match_score=None, gap_start=None, gap_extension=None,
+    boundary_bonus=None, consecutive_bonus=None, prefer_prefix=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn fuzzy_rank(
+    query: &str,
+    targets: Vec<(String, String, Vec<String>)>,
+    match_score: Option<f64>,
+    gap_start: Option<f64>,
+    gap_extension: Option<f64>,
+    boundary_bonus: Option<f64>,
+    consecutive_bonus: Option<f64>,
+    prefer_prefix: Option<bool>,
+) -> Vec<(usize, f64)> {
+    let config = matcher_config_from_args(
+        match_score, gap_start, gap_extension, boundary_bonus, consecutive_bonus, prefer_prefix,
+    );
+    let matcher = fuzzy::FuzzyMatcher::with_config(config);
     matcher.rank(query, &targets)
 }
 
+/// Rank targets against an fzf-style query: space-separated AND-ed terms,
+/// where a bare term is fuzzy, `'term` is a plain substring match, `^term`
+/// anchors to the start, `term$` anchors to the end, and `!term` excludes
+/// any target containing it (e.g. `"^http 'inject !deprecated"`).
+#[pyfunction]
+fn fuzzy_rank_query(query: &str, targets: Vec<(String, String, Vec<String>)>) -> Vec<(usize, f64)> {
+    let matcher = fuzzy::FuzzyMatcher::new();
+    matcher.rank_query(query, &targets)
+}
+
+/// Fold text through Unicode case folding, a homoglyph->ASCII table, and
+/// stripped zero-width/combining-mark characters, so evasions a fuzzer
+/// could produce (e.g. a Cyrillic "p\u{430}ssword") normalize to the plain
+/// ASCII text they're imitating.
+#[pyfunction]
+#[pyo3(name = "normalize_text")]
+fn normalize_text_py(text: &str) -> String {
+    normalize::normalize_text(text)
+}
+
 #[pymodule]
 fn akira_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(mutate_payload, m)?)?;
@@ -92,7 +231,11 @@ fn akira_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(find_patterns, m)?)?;
     m.add_function(wrap_pyfunction!(check_attack_success, m)?)?;
     m.add_function(wrap_pyfunction!(generate_payload_variations, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzz_campaign_step, m)?)?;
     m.add_function(wrap_pyfunction!(fuzzy_score, m)?)?;
     m.add_function(wrap_pyfunction!(fuzzy_rank, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy_rank_query, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_text_py, m)?)?;
+    m.add_class::<worker::MatchWorker>()?;
     Ok(())
 }
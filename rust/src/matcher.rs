@@ -1,19 +1,36 @@
 //! Fast multi-pattern matching using Aho-Corasick algorithm
 
+use crate::normalize::{normalize_text, normalize_with_offsets};
 use aho_corasick::AhoCorasick;
 
 pub struct PatternMatcher {
     patterns: Vec<String>,
     automaton: AhoCorasick,
+    normalize: bool,
 }
 
 impl PatternMatcher {
-    pub fn new(patterns: Vec<String>) -> Self {
-        let automaton = AhoCorasick::new(&patterns).expect("Failed to build pattern matcher");
-        Self { patterns, automaton }
+    /// Build a matcher for `patterns`. When `normalize` is set, both the
+    /// patterns and any text searched via [`PatternMatcher::find_all`] are
+    /// passed through [`normalize_text`] first, so homoglyph/zero-width
+    /// evasions of a pattern still match.
+    pub fn new(patterns: Vec<String>, normalize: bool) -> Self {
+        let compiled: Vec<String> = if normalize {
+            patterns.iter().map(|p| normalize_text(p)).collect()
+        } else {
+            patterns.clone()
+        };
+        let automaton = AhoCorasick::new(&compiled).expect("Failed to build pattern matcher");
+        Self { patterns, automaton, normalize }
     }
 
-    /// Find all pattern matches and their positions
+    /// Find all pattern matches and their positions.
+    ///
+    /// Positions are always byte offsets into the caller's original `text`.
+    /// When `normalize` is set, matching runs against the folded haystack
+    /// (so homoglyph/zero-width evasions are still caught), and each match
+    /// start is translated back through the fold's offset map rather than
+    /// being reported in normalized-string space.
     pub fn find_all(&self, text: &str) -> Vec<(String, Vec<usize>)> {
         let mut results: Vec<(String, Vec<usize>)> = self
             .patterns
@@ -21,9 +38,17 @@ impl PatternMatcher {
             .map(|p| (p.clone(), Vec::new()))
             .collect();
 
-        for mat in self.automaton.find_iter(text) {
-            let pattern_idx = mat.pattern().as_usize();
-            results[pattern_idx].1.push(mat.start());
+        if self.normalize {
+            let (haystack, offsets) = normalize_with_offsets(text);
+            for mat in self.automaton.find_iter(&haystack) {
+                let pattern_idx = mat.pattern().as_usize();
+                results[pattern_idx].1.push(offsets[mat.start()]);
+            }
+        } else {
+            for mat in self.automaton.find_iter(text) {
+                let pattern_idx = mat.pattern().as_usize();
+                results[pattern_idx].1.push(mat.start());
+            }
         }
 
         // Only return patterns that had matches
@@ -32,11 +57,13 @@ impl PatternMatcher {
 
     /// Check if any pattern matches
     pub fn has_match(&self, text: &str) -> bool {
-        self.automaton.is_match(text)
+        let haystack = if self.normalize { normalize_text(text) } else { text.to_string() };
+        self.automaton.is_match(&haystack)
     }
 
     /// Count total matches across all patterns
     pub fn count_matches(&self, text: &str) -> usize {
-        self.automaton.find_iter(text).count()
+        let haystack = if self.normalize { normalize_text(text) } else { text.to_string() };
+        self.automaton.find_iter(&haystack).count()
     }
 }
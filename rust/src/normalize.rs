@@ -0,0 +1,104 @@
+//! Unicode normalization for evasion-resistant pattern matching
+//!
+//! `PayloadFuzzer` deliberately produces Cyrillic homoglyphs, zero-width
+//! insertions, and full-width whitespace to probe filter evasion. Plain
+//! ASCII lowercasing doesn't see through those, so `PatternMatcher` and
+//! `ResponseAnalyzer` can opt into folding both the haystack and the
+//! patterns through this pass first: Unicode case folding, a
+//! homoglyph->ASCII table, stripped zero-width/combining-mark
+//! characters, and whitespace variants collapsed to a plain space.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Visually-similar characters (mostly Cyrillic, matching what
+/// `PayloadFuzzer::homoglyph_replace` produces) folded back to the ASCII
+/// letter they imitate.
+fn homoglyph_map() -> &'static HashMap<char, char> {
+    static MAP: OnceLock<HashMap<char, char>> = OnceLock::new();
+    MAP.get_or_init(|| {
+        [
+            ('а', 'a'), ('е', 'e'), ('о', 'o'), ('р', 'p'), ('с', 'c'), ('х', 'x'),
+            ('А', 'A'), ('Е', 'E'), ('О', 'O'), ('Р', 'P'), ('С', 'C'), ('Х', 'X'),
+            ('ѕ', 's'), ('і', 'i'), ('ј', 'j'), ('Ѕ', 'S'), ('І', 'I'), ('Ј', 'J'),
+        ]
+        .into_iter()
+        .collect()
+    })
+}
+
+/// Zero-width joiners/spaces and combining diacritical marks used to split
+/// tokens past naive filters.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200D}' | '\u{FEFF}' | '\u{00AD}' | '\u{2060}')
+        || ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+fn fold_char(c: char) -> char {
+    if c.is_whitespace() {
+        ' '
+    } else {
+        *homoglyph_map().get(&c).unwrap_or(&c)
+    }
+}
+
+/// Fold `input` so homoglyph, zero-width, and whitespace evasions collapse
+/// to the same representation as the plain ASCII text they're imitating.
+pub fn normalize_text(input: &str) -> String {
+    normalize_with_offsets(input).0
+}
+
+/// Like [`normalize_text`], but also returns a byte-offset map back to
+/// `input`: `map[i]` is the byte offset in `input` of the original char that
+/// produced the byte at `output[i]`. Folding can change both char count
+/// (zero-width and combining-mark chars are dropped, including the combining
+/// dot that lowercasing `İ` produces) and byte length (a folded ASCII char is shorter than
+/// the homoglyph it replaced), so a match found in the normalized string
+/// cannot be located in `input` by its raw offset alone; callers that need
+/// to report positions in the caller's original text use this map to
+/// translate them back.
+pub fn normalize_with_offsets(input: &str) -> (String, Vec<usize>) {
+    let mut output = String::with_capacity(input.len());
+    let mut map = Vec::with_capacity(input.len());
+
+    for (orig_start, c) in input.char_indices() {
+        if is_invisible(c) {
+            continue;
+        }
+        for folded in fold_char(c).to_lowercase() {
+            if is_invisible(folded) {
+                continue;
+            }
+            let byte_len = folded.len_utf8();
+            output.push(folded);
+            map.resize(map.len() + byte_len, orig_start);
+        }
+    }
+
+    (output, map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folds_homoglyphs() {
+        assert_eq!(normalize_text("pаssword"), "password");
+    }
+
+    #[test]
+    fn test_strips_zero_width() {
+        assert_eq!(normalize_text("pa\u{200B}ssword"), "password");
+    }
+
+    #[test]
+    fn test_collapses_whitespace_variants() {
+        assert_eq!(normalize_text("a\u{00A0}b\u{2003}c"), "a b c");
+    }
+
+    #[test]
+    fn test_case_folds() {
+        assert_eq!(normalize_text("SECRET"), "secret");
+    }
+}
@@ -0,0 +1,139 @@
+//! Streaming background matcher for interactive module search
+//!
+//! Every other entry point in this crate builds its matcher and scores the
+//! whole input in one blocking call, which is wasteful when a user is
+//! typing a query interactively against thousands of modules. `MatchWorker`
+//! instead holds the corpus across calls: new items stream in through an
+//! injector channel (so the catalog can grow while a search is in flight)
+//! and are re-ranked in the background (on a drain thread for pushed items,
+//! on the rayon pool for a new query), so reading back results is
+//! a cheap snapshot of whatever has been computed so far rather than a
+//! blocking rebuild.
+
+use crate::fuzzy::FuzzyMatcher;
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+type Target = (String, String, Vec<String>);
+
+#[pyclass]
+pub struct MatchWorker {
+    injector: Sender<Target>,
+    // Items pushed but not yet drained+rescored by the background thread.
+    pending: Arc<AtomicUsize>,
+    corpus: Arc<Mutex<Vec<Target>>>,
+    query: Arc<Mutex<String>>,
+    results: Arc<Mutex<Vec<(usize, f64)>>>,
+}
+
+impl MatchWorker {
+    /// Re-rank `corpus` against whatever query is current and publish it to
+    /// `results`. Runs on the rayon pool (after `query`) or on the drain
+    /// thread (after `push_items`), never on the caller's thread.
+    fn rescore(query: &Arc<Mutex<String>>, results: &Arc<Mutex<Vec<(usize, f64)>>>, corpus: &[Target]) {
+        let pattern = query.lock().unwrap().clone();
+        if pattern.is_empty() {
+            // No query means nothing should rank, not "whatever ranked
+            // last" -- clear rather than leaving stale results behind
+            // after a caller empties the search box.
+            results.lock().unwrap().clear();
+            return;
+        }
+        let matcher = FuzzyMatcher::new();
+        let ranked = matcher.rank(&pattern, corpus);
+        *results.lock().unwrap() = ranked;
+    }
+}
+
+#[pymethods]
+impl MatchWorker {
+    #[new]
+    pub fn new() -> Self {
+        let (injector, inbox) = mpsc::channel::<Target>();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let corpus: Arc<Mutex<Vec<Target>>> = Arc::new(Mutex::new(Vec::new()));
+        let query = Arc::new(Mutex::new(String::new()));
+        let results = Arc::new(Mutex::new(Vec::new()));
+
+        // Background drain: absorbs injected items into the corpus and
+        // re-ranks against the current query, so the catalog can keep
+        // growing without ever blocking a caller on a rebuild. Items are
+        // drained in whole batches (one blocking `recv` to wait for the
+        // next item, then `try_recv` until the channel runs dry) and
+        // rescored once per batch rather than once per item, so a burst of
+        // N pushed items costs one corpus clone and one rank() call instead
+        // of N of each.
+        let pending_bg = Arc::clone(&pending);
+        let corpus_bg = Arc::clone(&corpus);
+        let query_bg = Arc::clone(&query);
+        let results_bg = Arc::clone(&results);
+        std::thread::spawn(move || {
+            while let Ok(first) = inbox.recv() {
+                let mut batch = vec![first];
+                while let Ok(item) = inbox.try_recv() {
+                    batch.push(item);
+                }
+                let batch_len = batch.len();
+
+                let snapshot = {
+                    let mut guard = corpus_bg.lock().unwrap();
+                    guard.extend(batch);
+                    guard.clone()
+                };
+                Self::rescore(&query_bg, &results_bg, &snapshot);
+                pending_bg.fetch_sub(batch_len, Ordering::SeqCst);
+            }
+        });
+
+        Self {
+            injector,
+            pending,
+            corpus,
+            query,
+            results,
+        }
+    }
+
+    /// Number of items that have been pushed via `push_items` but not yet
+    /// absorbed into the corpus and rescored by the background drain
+    /// thread. An interactive caller can poll this to know whether
+    /// `results` still reflects the full catalog or a still-draining one.
+    pub fn pending_items(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Push new `(name, description, tags)` targets into the corpus. Safe
+    /// to call while a `query`/`results` call is in progress elsewhere.
+    pub fn push_items(&self, items: Vec<Target>) {
+        self.pending.fetch_add(items.len(), Ordering::SeqCst);
+        for item in items {
+            let _ = self.injector.send(item);
+        }
+    }
+
+    /// Re-query the corpus gathered so far on the rayon pool. Returns
+    /// immediately; read the outcome via `results`.
+    pub fn query(&self, pattern: String) {
+        *self.query.lock().unwrap() = pattern;
+        let corpus = Arc::clone(&self.corpus);
+        let query = Arc::clone(&self.query);
+        let results = Arc::clone(&self.results);
+        rayon::spawn(move || {
+            let snapshot = corpus.lock().unwrap().clone();
+            Self::rescore(&query, &results, &snapshot);
+        });
+    }
+
+    /// Best `n` ranked results computed so far, without blocking on a rebuild.
+    pub fn results(&self, n: usize) -> Vec<(usize, f64)> {
+        self.results.lock().unwrap().iter().take(n).cloned().collect()
+    }
+}
+
+impl Default for MatchWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}